@@ -0,0 +1,57 @@
+use anyhow::Result;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use std::io::Cursor;
+
+static CLICK_SOUND: &[u8] = include_bytes!("../audio/click.wav");
+static VICTORY_SOUND: &[u8] = include_bytes!("../audio/victory.ogg");
+
+/// Owns the output stream for the session; sounds keep playing smoothly as long as this
+/// stays alive. Clone `handle()` out to let other parts of the program trigger playback.
+pub struct Audio {
+    _stream: OutputStream,
+    handle: AudioHandle,
+}
+
+impl Audio {
+    pub fn new() -> Result<Self> {
+        let (stream, stream_handle) = OutputStream::try_default()?;
+        Ok(Self {
+            _stream: stream,
+            handle: AudioHandle(stream_handle),
+        })
+    }
+
+    pub fn handle(&self) -> AudioHandle {
+        self.handle.clone()
+    }
+
+    pub fn play_victory(&self) {
+        self.handle.play_victory();
+    }
+}
+
+#[derive(Clone)]
+pub struct AudioHandle(OutputStreamHandle);
+
+impl AudioHandle {
+    /// Fires the click and returns immediately so gameplay never waits on it.
+    pub fn play_click(&self) {
+        if let Some(sink) = self.sink_with(CLICK_SOUND) {
+            sink.detach();
+        }
+    }
+
+    /// Blocks until the victory jingle finishes, so it isn't cut off by the process exiting
+    /// right after the board is solved.
+    pub fn play_victory(&self) {
+        if let Some(sink) = self.sink_with(VICTORY_SOUND) {
+            sink.sleep_until_end();
+        }
+    }
+
+    fn sink_with(&self, bytes: &'static [u8]) -> Option<Sink> {
+        let sink = Sink::try_new(&self.0).ok()?;
+        sink.append(Decoder::new(Cursor::new(bytes)).ok()?);
+        Some(sink)
+    }
+}
@@ -1,12 +1,19 @@
+#[cfg(feature = "audio")]
+use crate::audio::AudioHandle;
 use anyhow::Result;
 use crossterm::style::Color;
-use crossterm::{cursor, style};
-use image::GenericImageView;
+use crossterm::{cursor, style, terminal};
+use image::{AnimationDecoder, GenericImageView, ImageFormat, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_rect_mut, draw_text_mut};
+use imageproc::rect::Rect;
 use itertools::Itertools;
 use rand::prelude::*;
-use std::io::Write;
+use rusttype::{Font, Scale};
+use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
@@ -15,10 +22,15 @@ pub enum Direction {
 }
 
 pub struct Board {
-    img_pixels: Vec<Color>,
+    frames: Vec<Vec<Color>>,
+    delays: Vec<Duration>,
+    current_frame: usize,
     img_size: usize,
     board_size: usize,
     tiles: Vec<usize>,
+    move_count: usize,
+    #[cfg(feature = "audio")]
+    audio: Option<AudioHandle>,
 }
 
 impl Board {
@@ -30,6 +42,28 @@ impl Board {
         self.tiles.iter().enumerate().all(|(i, t)| i == *t)
     }
 
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+
+    pub fn frame_delay(&self) -> Duration {
+        self.delays[self.current_frame]
+    }
+
+    /// Advances to the next frame of the source image and redraws every non-blank tile.
+    pub fn draw_frame<W: Write>(&mut self, w: &mut W) -> Result<()> {
+        self.current_frame = (self.current_frame + 1) % self.frames.len();
+
+        for i in 0..self.board_size * self.board_size {
+            if self.tiles[i] != blank_tile_num(self.board_size) {
+                self.draw_tile(w, i)?;
+            }
+        }
+        w.flush()?;
+
+        Ok(())
+    }
+
     pub fn draw<W: Write>(&self, w: &mut W) -> Result<()> {
         for i in 0..self.board_size * self.board_size {
             self.draw_tile(w, i)?;
@@ -37,8 +71,47 @@ impl Board {
         Ok(())
     }
 
+    /// Row just below the board, reserved for the HUD.
+    fn hud_row(&self) -> u16 {
+        self.img_size as u16 / 2
+    }
+
+    /// Draws the move counter and elapsed time on the HUD line, highlighting them once the
+    /// board is solved.
+    pub fn draw_hud<W: Write>(&self, w: &mut W, elapsed: Duration) -> Result<()> {
+        let secs = elapsed.as_secs();
+        let text = format!(
+            "moves: {}   time: {:02}:{:02}",
+            self.move_count,
+            secs / 60,
+            secs % 60
+        );
+
+        crossterm::queue!(
+            w,
+            cursor::MoveTo(0, self.hud_row()),
+            terminal::Clear(terminal::ClearType::UntilNewLine)
+        )?;
+        if self.is_solved() {
+            crossterm::queue!(w, style::SetAttribute(style::Attribute::Bold))?;
+        }
+        crossterm::queue!(
+            w,
+            style::Print(text),
+            style::SetAttribute(style::Attribute::Reset)
+        )?;
+        w.flush()?;
+
+        Ok(())
+    }
+
     pub fn move_and_draw_tiles<W: Write>(&mut self, w: &mut W, direction: Direction) -> Result<()> {
         if let Some((a, b)) = self.move_tiles(direction) {
+            #[cfg(feature = "audio")]
+            if let Some(audio) = &self.audio {
+                audio.play_click();
+            }
+
             self.draw_tile(w, a)?;
             self.draw_tile(w, b)?;
             w.flush()?;
@@ -46,6 +119,75 @@ impl Board {
         Ok(())
     }
 
+    /// Computes an optimal (or near-optimal) solution from the current scramble using
+    /// Iterative Deepening A* over blank-moves, on a background thread so the caller's event
+    /// loop stays responsive. The result is paired with `move_count` at the time of the call,
+    /// so the caller can tell whether any moves happened in the meantime and the solution is
+    /// now stale.
+    pub fn start_solve(&self) -> crossbeam_channel::Receiver<(usize, Vec<Direction>)> {
+        let n = self.board_size;
+        let tiles = self.tiles.clone();
+        let move_count = self.move_count;
+
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        std::thread::spawn(move || {
+            let _ = tx.send((move_count, solve_tiles(n, tiles)));
+        });
+        rx
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.move_count
+    }
+
+    pub fn click_and_draw_tiles<W: Write>(&mut self, w: &mut W, col: u16, row: u16) -> Result<()> {
+        if let Some(direction) = self.click_direction(col, row) {
+            self.move_and_draw_tiles(w, direction)?;
+        }
+        Ok(())
+    }
+
+    fn tile_at(&self, col: u16, row: u16) -> Option<usize> {
+        let tile_width = self.img_size / self.board_size;
+        let tile_height = tile_width / 2;
+
+        let tile_x = col as usize / tile_width;
+        let tile_y = row as usize / tile_height;
+
+        if tile_x < self.board_size && tile_y < self.board_size {
+            Some(tile_x + tile_y * self.board_size)
+        } else {
+            None
+        }
+    }
+
+    fn click_direction(&self, col: u16, row: u16) -> Option<Direction> {
+        let clicked_tile = self.tile_at(col, row)?;
+
+        let blank_tile = self
+            .tiles
+            .iter()
+            .position(|t| *t == blank_tile_num(self.board_size))
+            .unwrap();
+
+        let (blank_x, blank_y) = (
+            (blank_tile % self.board_size) as isize,
+            (blank_tile / self.board_size) as isize,
+        );
+        let (clicked_x, clicked_y) = (
+            (clicked_tile % self.board_size) as isize,
+            (clicked_tile / self.board_size) as isize,
+        );
+
+        match (clicked_x - blank_x, clicked_y - blank_y) {
+            (0, 1) => Some(Direction::Up),
+            (0, -1) => Some(Direction::Down),
+            (1, 0) => Some(Direction::Left),
+            (-1, 0) => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
     pub fn move_tiles(&mut self, direction: Direction) -> Option<(usize, usize)> {
         let (dx, dy) = match direction {
             Direction::Up => (0, 1),
@@ -72,6 +214,7 @@ impl Board {
         if movable_x && movable_y {
             let dest_tile = dest_x as usize + dest_y as usize * self.board_size;
             self.tiles.swap(blank_tile, dest_tile);
+            self.move_count += 1;
 
             Some((blank_tile, dest_tile))
         } else {
@@ -114,8 +257,9 @@ impl Board {
         for (screen_y, img_y) in y_pos {
             crossterm::queue!(w, cursor::MoveTo(screen_x as u16, screen_y as u16))?;
 
-            let upper = self.img_pixels[img_x + img_y * self.img_size..][..width].iter();
-            let lower = self.img_pixels[img_x + (img_y + 1) * self.img_size..][..width].iter();
+            let pixels = &self.frames[self.current_frame];
+            let upper = pixels[img_x + img_y * self.img_size..][..width].iter();
+            let lower = pixels[img_x + (img_y + 1) * self.img_size..][..width].iter();
             let runs = upper
                 .zip(lower)
                 .map(|x| (x, 1))
@@ -163,6 +307,9 @@ pub struct BoardBuilder {
     crop_image: bool,
     terminal_size: (u16, u16),
     board_size: usize,
+    show_numbers: bool,
+    #[cfg(feature = "audio")]
+    audio: Option<AudioHandle>,
 }
 
 impl BoardBuilder {
@@ -172,6 +319,9 @@ impl BoardBuilder {
             crop_image: false,
             terminal_size: (80, 24),
             board_size: 4,
+            show_numbers: false,
+            #[cfg(feature = "audio")]
+            audio: None,
         }
     }
 
@@ -192,16 +342,27 @@ impl BoardBuilder {
             return Err(anyhow::anyhow!("n is too large"));
         }
 
-        let pixels = load_image(self.image.as_ref(), img_size as u32, self.crop_image)?;
+        let (frames, delays) = load_image(
+            self.image.as_ref(),
+            img_size as u32,
+            self.crop_image,
+            self.board_size,
+            self.show_numbers,
+        )?;
 
         let tiles = generate_tiles(self.board_size);
         assert!(is_solvable(self.board_size, &tiles));
 
         let board = Board {
-            img_pixels: pixels,
+            frames,
+            delays,
+            current_frame: 0,
             img_size,
             board_size: self.board_size,
             tiles,
+            move_count: 0,
+            #[cfg(feature = "audio")]
+            audio: self.audio.clone(),
         };
         Ok(board)
     }
@@ -225,52 +386,279 @@ impl BoardBuilder {
         self.board_size = board_size;
         self
     }
+
+    pub fn show_numbers(&mut self, yes: bool) -> &mut Self {
+        self.show_numbers = yes;
+        self
+    }
+
+    #[cfg(feature = "audio")]
+    pub fn audio(&mut self, audio: AudioHandle) -> &mut Self {
+        self.audio = Some(audio);
+        self
+    }
 }
 
 static DEFAULT_IMAGE: &[u8] = include_bytes!("../img/default.png");
+static NUMBER_FONT: &[u8] = include_bytes!("../img/number_font.ttf");
+
+/// Loads `path` (or the bundled default image) and decodes it into per-frame pixel grids
+/// sized to `size`, cropping to a square first if `crop` is set. Animated GIF/WebP images
+/// yield one entry per frame with that frame's delay; anything else yields a single frame.
+/// When `show_numbers` is set, each tile's goal index is stamped onto its region before the
+/// image is diced into tiles, so the blank stays the only unlabeled cell.
+fn load_image<P: AsRef<Path>>(
+    path: Option<P>,
+    size: u32,
+    crop: bool,
+    board_size: usize,
+    show_numbers: bool,
+) -> Result<(Vec<Vec<Color>>, Vec<Duration>)> {
+    let (format, bytes) = match path {
+        Some(path) => {
+            let bytes = std::fs::read(path.as_ref())?;
+            let format =
+                ImageFormat::from_path(path.as_ref()).or_else(|_| image::guess_format(&bytes))?;
+            (format, bytes)
+        }
+        None => (ImageFormat::Png, DEFAULT_IMAGE.to_vec()),
+    };
 
-fn load_image<P: AsRef<Path>>(path: Option<P>, size: u32, crop: bool) -> Result<Vec<Color>> {
-    let mut img = if let Some(path) = path {
-        image::io::Reader::open(path)?
-            .with_guessed_format()?
-            .decode()?
-    } else {
-        image::load_from_memory(DEFAULT_IMAGE)?
+    let raw_frames: Vec<image::Frame> = match format {
+        ImageFormat::Gif => image::codecs::gif::GifDecoder::new(Cursor::new(&bytes))?
+            .into_frames()
+            .collect_frames()?,
+        ImageFormat::WebP => image::codecs::webp::WebPDecoder::new(Cursor::new(&bytes))?
+            .into_frames()
+            .collect_frames()?,
+        _ => vec![image::Frame::new(
+            image::load_from_memory_with_format(&bytes, format)?.to_rgba8(),
+        )],
     };
 
-    if crop {
-        let (width, height) = img.dimensions();
-        let crop_size = width.min(height);
-        img = img.crop(0, 0, crop_size, crop_size);
-    }
-    let img = img.thumbnail_exact(size, size);
+    let mut frames = Vec::with_capacity(raw_frames.len());
+    let mut delays = Vec::with_capacity(raw_frames.len());
 
-    let pixels: Vec<_> = img
-        .pixels()
-        .map(|(_, _, data)| {
-            if data[3] == 0 {
-                Color::Reset
-            } else {
-                Color::Rgb {
-                    r: data[0],
-                    g: data[1],
-                    b: data[2],
+    for frame in raw_frames {
+        delays.push(frame.delay().into());
+
+        let mut img = image::DynamicImage::ImageRgba8(frame.into_buffer());
+        if crop {
+            let (width, height) = img.dimensions();
+            let crop_size = width.min(height);
+            img = img.crop(0, 0, crop_size, crop_size);
+        }
+        let img = img.thumbnail_exact(size, size);
+        let mut img = img.to_rgba8();
+        if show_numbers {
+            draw_tile_numbers(&mut img, size, board_size);
+        }
+
+        let pixels: Vec<_> = img
+            .enumerate_pixels()
+            .map(|(_, _, data)| {
+                if data[3] == 0 {
+                    Color::Reset
+                } else {
+                    Color::Rgb {
+                        r: data[0],
+                        g: data[1],
+                        b: data[2],
+                    }
                 }
-            }
-        })
-        .collect();
+            })
+            .collect();
 
-    let (width, height) = img.dimensions();
-    assert_eq!(width, height);
-    assert_eq!(width, size);
+        let (width, height) = img.dimensions();
+        assert_eq!(width, height);
+        assert_eq!(width, size);
+
+        frames.push(pixels);
+    }
 
-    Ok(pixels)
+    Ok((frames, delays))
+}
+
+/// Stamps each non-blank tile's goal index onto its region of `img`, on a solid badge so the
+/// digits stay legible once `draw_tile` downsamples two rows into one line of half-blocks.
+fn draw_tile_numbers(img: &mut RgbaImage, size: u32, board_size: usize) {
+    let font = Font::try_from_bytes(NUMBER_FONT).expect("bundled number font is valid");
+    let tile_size = (size / board_size as u32) as i32;
+    let badge_size = (tile_size / 2) as u32;
+    let scale = Scale::uniform(badge_size as f32 * 0.8);
+
+    for v in 0..board_size * board_size - 1 {
+        let (tile_x, tile_y) = ((v % board_size) as i32, (v / board_size) as i32);
+        let (x, y) = (tile_x * tile_size, tile_y * tile_size);
+
+        draw_filled_rect_mut(
+            img,
+            Rect::at(x, y).of_size(badge_size, badge_size),
+            Rgba([0, 0, 0, 200]),
+        );
+        draw_text_mut(
+            img,
+            Rgba([255, 255, 255, 255]),
+            x + badge_size as i32 / 4,
+            y,
+            scale,
+            &font,
+            &v.to_string(),
+        );
+    }
 }
 
 const fn blank_tile_num(n: usize) -> usize {
     n * n - 1
 }
 
+fn direction_offset(direction: Direction) -> (isize, isize) {
+    match direction {
+        Direction::Up => (0, 1),
+        Direction::Down => (0, -1),
+        Direction::Left => (1, 0),
+        Direction::Right => (-1, 0),
+    }
+}
+
+fn opposite_direction(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::Down => Direction::Up,
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left,
+    }
+}
+
+/// Slides the blank one step in `direction`, returning `false` if that would move it off
+/// the board. Sliding in the opposite direction undoes the move.
+fn slide(n: usize, tiles: &mut [usize], direction: Direction) -> bool {
+    let (dx, dy) = direction_offset(direction);
+    let blank = tiles.iter().position(|t| *t == blank_tile_num(n)).unwrap();
+    let (blank_x, blank_y) = ((blank % n) as isize, (blank / n) as isize);
+    let (dest_x, dest_y) = (blank_x + dx, blank_y + dy);
+
+    if (0..n as isize).contains(&dest_x) && (0..n as isize).contains(&dest_y) {
+        let dest = dest_x as usize + dest_y as usize * n;
+        tiles.swap(blank, dest);
+        true
+    } else {
+        false
+    }
+}
+
+/// Sum of Manhattan distances of every non-blank tile from its goal cell, augmented with
+/// linear-conflict: tiles sharing a row or column with their goal but in reversed order
+/// must pass each other, so each such pair adds 2 extra moves.
+fn heuristic(n: usize, tiles: &[usize]) -> usize {
+    let blank = blank_tile_num(n);
+    let goal_pos = |v: usize| (v % n, v / n);
+
+    let manhattan: usize = tiles
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| **t != blank)
+        .map(|(i, t)| {
+            let (x, y) = (i % n, i / n);
+            let (gx, gy) = goal_pos(*t);
+            x.abs_diff(gx) + y.abs_diff(gy)
+        })
+        .sum();
+
+    let mut conflicts = 0;
+    for row in 0..n {
+        let line: Vec<_> = (0..n)
+            .map(|col| tiles[col + row * n])
+            .filter(|t| *t != blank && goal_pos(*t).1 == row)
+            .collect();
+        conflicts += count_conflicts(&line, |t| goal_pos(t).0);
+    }
+    for col in 0..n {
+        let line: Vec<_> = (0..n)
+            .map(|row| tiles[col + row * n])
+            .filter(|t| *t != blank && goal_pos(*t).0 == col)
+            .collect();
+        conflicts += count_conflicts(&line, |t| goal_pos(t).1);
+    }
+
+    manhattan + conflicts * 2
+}
+
+fn count_conflicts(line: &[usize], goal_index: impl Fn(usize) -> usize) -> usize {
+    let mut conflicts = 0;
+    for i in 0..line.len() {
+        for j in i + 1..line.len() {
+            if goal_index(line[i]) > goal_index(line[j]) {
+                conflicts += 1;
+            }
+        }
+    }
+    conflicts
+}
+
+/// Runs IDA* to completion and returns the move path from `tiles` to the solved state.
+fn solve_tiles(n: usize, mut tiles: Vec<usize>) -> Vec<Direction> {
+    let mut threshold = heuristic(n, &tiles);
+    let mut path = Vec::new();
+
+    loop {
+        match search(n, &mut tiles, 0, threshold, None, &mut path) {
+            SearchOutcome::Found => return path,
+            SearchOutcome::Exceeded(next_threshold) => threshold = next_threshold,
+        }
+    }
+}
+
+enum SearchOutcome {
+    Found,
+    Exceeded(usize),
+}
+
+/// Depth-first search bounded by `threshold`, backtracking through blank-slides. Returns
+/// either `Found`, with the solved path left in `path`, or the smallest `f` seen that
+/// exceeded the threshold, so the caller can raise it and retry.
+fn search(
+    n: usize,
+    tiles: &mut [usize],
+    g: usize,
+    threshold: usize,
+    last_move: Option<Direction>,
+    path: &mut Vec<Direction>,
+) -> SearchOutcome {
+    let f = g + heuristic(n, tiles);
+    if f > threshold {
+        return SearchOutcome::Exceeded(f);
+    }
+    if tiles.iter().enumerate().all(|(i, t)| i == *t) {
+        return SearchOutcome::Found;
+    }
+
+    let mut min_exceeded = usize::MAX;
+    for direction in [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ] {
+        if last_move == Some(opposite_direction(direction)) {
+            continue;
+        }
+        if !slide(n, tiles, direction) {
+            continue;
+        }
+
+        path.push(direction);
+        match search(n, tiles, g + 1, threshold, Some(direction), path) {
+            SearchOutcome::Found => return SearchOutcome::Found,
+            SearchOutcome::Exceeded(next) => min_exceeded = min_exceeded.min(next),
+        }
+        path.pop();
+        slide(n, tiles, opposite_direction(direction));
+    }
+
+    SearchOutcome::Exceeded(min_exceeded)
+}
+
 fn generate_tiles(n: usize) -> Vec<usize> {
     let mut rng = rand::thread_rng();
     let mut tiles: Vec<_> = (0..n * n).collect();
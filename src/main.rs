@@ -1,13 +1,20 @@
+#[cfg(feature = "audio")]
+mod audio;
 mod board;
 
 use anyhow::Result;
 use board::{BoardBuilder, Direction};
-use crossterm::event::{Event, KeyCode, KeyModifiers};
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 use crossterm::{cursor, event, style, terminal};
+use std::collections::VecDeque;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use structopt::StructOpt;
 
+const SOLVE_STEP_INTERVAL: Duration = Duration::from_millis(200);
+const HUD_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
 #[derive(StructOpt)]
 #[structopt(
     author = env!("CARGO_PKG_AUTHORS"),
@@ -25,20 +32,39 @@ struct Opt {
     /// Crops an image instead of stretching
     #[structopt(short, long)]
     crop: bool,
+
+    /// Overlays each tile's goal number
+    #[structopt(long)]
+    numbers: bool,
+
+    /// Disables sound effects
+    #[cfg(feature = "audio")]
+    #[structopt(long)]
+    mute: bool,
 }
 
 fn main() -> Result<()> {
     let opt = Opt::from_args();
     anyhow::ensure!(opt.n > 1, "n must be 2 or larger");
 
+    // Sound is purely additive feedback, so a missing/unsupported audio device shouldn't
+    // stop the game from starting; fall back to silence instead of propagating the error.
+    #[cfg(feature = "audio")]
+    let audio = (!opt.mute).then(|| audio::Audio::new().ok()).flatten();
+
     let mut builder = BoardBuilder::new();
     builder
         .board_size(opt.n)
         .crop_image(opt.crop)
+        .show_numbers(opt.numbers)
         .terminal_size(terminal::size()?);
     if let Some(file) = opt.file {
         builder.image(file);
     }
+    #[cfg(feature = "audio")]
+    if let Some(audio) = &audio {
+        builder.audio(audio.handle());
+    }
     let mut board = builder.build()?;
 
     let (tx, rx) = crossbeam_channel::unbounded();
@@ -50,38 +76,111 @@ fn main() -> Result<()> {
 
     let mut stdout = setup_terminal()?;
     board.draw(&mut stdout)?;
+    board.draw_hud(&mut stdout, Duration::default())?;
     stdout.flush()?;
 
+    let solve_ticker = crossbeam_channel::tick(SOLVE_STEP_INTERVAL);
+    let mut solution: Option<VecDeque<Direction>> = None;
+    let mut solve_result_rx = crossbeam_channel::never();
+    let mut solving = false;
+
+    let mut frame_ticker = if board.is_animated() {
+        crossbeam_channel::tick(board.frame_delay())
+    } else {
+        crossbeam_channel::never()
+    };
+
+    let hud_ticker = crossbeam_channel::tick(HUD_REFRESH_INTERVAL);
+    let mut start: Option<Instant> = None;
+
     loop {
-        if let Event::Key(key) = rx.recv()? {
-            match (key.modifiers, key.code) {
-                (_, KeyCode::Esc)
-                | (KeyModifiers::CONTROL, KeyCode::Char('c'))
-                | (_, KeyCode::Char('q')) => break,
-                (_, KeyCode::Up) | (_, KeyCode::Char('k')) | (_, KeyCode::Char('w')) => {
-                    board.move_and_draw_tiles(&mut stdout, Direction::Up)?;
+        crossbeam_channel::select! {
+            recv(rx) -> event => {
+                let event = event?;
+                start.get_or_insert_with(Instant::now);
+
+                match event {
+                    Event::Key(key) => match (key.modifiers, key.code) {
+                        (_, KeyCode::Esc)
+                        | (KeyModifiers::CONTROL, KeyCode::Char('c'))
+                        | (_, KeyCode::Char('q')) => break,
+                        (_, KeyCode::Up) | (_, KeyCode::Char('k')) | (_, KeyCode::Char('w')) => {
+                            solution = None;
+                            board.move_and_draw_tiles(&mut stdout, Direction::Up)?;
+                        }
+                        (_, KeyCode::Down) | (_, KeyCode::Char('j')) | (_, KeyCode::Char('s')) => {
+                            solution = None;
+                            board.move_and_draw_tiles(&mut stdout, Direction::Down)?;
+                        }
+                        (_, KeyCode::Left) | (_, KeyCode::Char('h')) | (_, KeyCode::Char('a')) => {
+                            solution = None;
+                            board.move_and_draw_tiles(&mut stdout, Direction::Left)?;
+                        }
+                        (_, KeyCode::Right) | (_, KeyCode::Char('l')) | (_, KeyCode::Char('d')) => {
+                            solution = None;
+                            board.move_and_draw_tiles(&mut stdout, Direction::Right)?;
+                        }
+                        (_, KeyCode::Char(' ')) if !solving => {
+                            solution = None;
+                            solving = true;
+                            solve_result_rx = board.start_solve();
+                        }
+                        _ => (),
+                    },
+                    Event::Mouse(mouse) => {
+                        if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+                            solution = None;
+                            board.click_and_draw_tiles(&mut stdout, mouse.column, mouse.row)?;
+                        }
+                    }
+                    _ => (),
                 }
-                (_, KeyCode::Down) | (_, KeyCode::Char('j')) | (_, KeyCode::Char('s')) => {
-                    board.move_and_draw_tiles(&mut stdout, Direction::Down)?;
+
+                board.draw_hud(&mut stdout, start.unwrap().elapsed())?;
+            },
+            recv(solve_ticker) -> _ => {
+                if let Some(moves) = &mut solution {
+                    match moves.pop_front() {
+                        Some(direction) => board.move_and_draw_tiles(&mut stdout, direction)?,
+                        None => solution = None,
+                    }
+                    if let Some(start) = start {
+                        board.draw_hud(&mut stdout, start.elapsed())?;
+                    }
                 }
-                (_, KeyCode::Left) | (_, KeyCode::Char('h')) | (_, KeyCode::Char('a')) => {
-                    board.move_and_draw_tiles(&mut stdout, Direction::Left)?;
+            },
+            recv(solve_result_rx) -> result => {
+                solve_result_rx = crossbeam_channel::never();
+                solving = false;
+                if let Ok((move_count, path)) = result {
+                    if move_count == board.move_count() {
+                        solution = Some(path.into());
+                    }
                 }
-                (_, KeyCode::Right) | (_, KeyCode::Char('l')) | (_, KeyCode::Char('d')) => {
-                    board.move_and_draw_tiles(&mut stdout, Direction::Right)?;
+            },
+            recv(frame_ticker) -> _ => {
+                board.draw_frame(&mut stdout)?;
+                frame_ticker = crossbeam_channel::tick(board.frame_delay());
+            },
+            recv(hud_ticker) -> _ => {
+                if let Some(start) = start {
+                    board.draw_hud(&mut stdout, start.elapsed())?;
                 }
-                _ => (),
             }
+        }
 
-            if board.is_solved() {
-                break;
+        if board.is_solved() {
+            #[cfg(feature = "audio")]
+            if let Some(audio) = &audio {
+                audio.play_victory();
             }
+            break;
         }
     }
 
     crossterm::queue!(
         stdout,
-        cursor::MoveTo(0, board.image_size() as u16 / 2),
+        cursor::MoveTo(0, board.image_size() as u16 / 2 + 1),
         style::ResetColor
     )?;
     stdout.flush()?;
@@ -97,14 +196,15 @@ fn setup_terminal() -> Result<io::Stdout> {
     crossterm::queue!(
         stdout,
         terminal::Clear(terminal::ClearType::All),
-        cursor::Hide
+        cursor::Hide,
+        event::EnableMouseCapture
     )?;
 
     Ok(stdout)
 }
 
 fn cleanup_terminal<W: Write>(mut w: W) -> Result<()> {
-    crossterm::queue!(w, cursor::Show)?;
+    crossterm::queue!(w, cursor::Show, event::DisableMouseCapture)?;
     terminal::disable_raw_mode()?;
 
     Ok(())